@@ -5,8 +5,9 @@ use super::{AccountInfo, KeyfileStorage, generate_filename};
 use super::error::Error;
 use core::Address;
 use keystore::KeyFile;
-use rocksdb::{DB, DBVector, IteratorMode};
+use rocksdb::{DB, DBVector, IteratorMode, WriteBatch};
 use rustc_serialize::json;
+use std::collections::HashMap;
 use std::path::Path;
 use std::str;
 
@@ -65,6 +66,75 @@ impl DbStorage {
 
         Ok(val)
     }
+
+    /// Start a batch of staged `put`/`delete` operations
+    ///
+    /// Nothing reaches the database until [`Batch::commit`](struct.Batch.html#method.commit)
+    /// is called; an uncommitted batch is simply discarded on drop.
+    pub fn begin(&self) -> Batch {
+        Batch {
+            db: self,
+            overlay: HashMap::new(),
+        }
+    }
+}
+
+/// A batch of staged writes against a [`DbStorage`](struct.DbStorage.html)
+///
+/// `put`/`delete` accumulate in an in-memory overlay; `search_by_address`
+/// called through the batch sees those pending writes (and never returns an
+/// entry staged for deletion) before anything touches the database. All
+/// staged operations flush atomically via a single RocksDB `WriteBatch` on
+/// `commit`.
+pub struct Batch<'a> {
+    db: &'a DbStorage,
+    overlay: HashMap<Address, Option<KeyFile>>,
+}
+
+impl<'a> Batch<'a> {
+    /// Stage a `put`, overriding any earlier staged op for the same address;
+    /// a keyfile without an `address` is not staged
+    pub fn put(&mut self, kf: &KeyFile) {
+        if let Some(addr) = kf.address {
+            self.overlay.insert(addr, Some(kf.clone()));
+        }
+    }
+
+    /// Stage a `delete`, overriding any earlier staged op for the same address
+    pub fn delete(&mut self, addr: &Address) {
+        self.overlay.insert(*addr, None);
+    }
+
+    /// Look up `addr`, preferring a pending overlay write over the database
+    pub fn search_by_address(&self, addr: &Address) -> Result<KeyFile, Error> {
+        match self.overlay.get(addr) {
+            Some(&Some(ref kf)) => Ok(kf.clone()),
+            Some(&None) => Err(Error::NotFound(format!("{}", addr))),
+            None => self.db.search_by_address(addr),
+        }
+    }
+
+    /// Flush every staged op as a single atomic `WriteBatch`
+    pub fn commit(self) -> Result<(), Error> {
+        let mut wb = WriteBatch::default();
+
+        for (addr, entry) in &self.overlay {
+            match *entry {
+                Some(ref kf) => {
+                    let json = json::encode(kf)?;
+                    let val = generate_filename(&kf.uuid.to_string()) + SEPARATOR + &json;
+                    wb.put(addr.as_ref(), val.as_bytes())?;
+                }
+                None => {
+                    wb.delete(addr.as_ref())?;
+                }
+            }
+        }
+
+        self.db.db.write(wb)?;
+
+        Ok(())
+    }
 }
 
 impl KeyfileStorage for DbStorage {
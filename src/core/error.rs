@@ -0,0 +1,32 @@
+//! # Core domain errors
+
+use std::{error, fmt};
+
+/// Core domain level errors
+#[derive(Debug, Clone)]
+pub enum Error {
+    /// Input isn't a valid hex string of the expected length
+    InvalidHexLength(String),
+
+    /// EIP-55 checksum of a mixed-case address didn't match
+    InvalidChecksum(String),
+
+    /// Failure from the underlying secp256k1 implementation
+    EcdsaCrypto(String),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Error::InvalidHexLength(ref str) => write!(f, "Invalid hex data length: {}", str),
+            Error::InvalidChecksum(ref str) => write!(f, "Invalid address checksum: {}", str),
+            Error::EcdsaCrypto(ref str) => write!(f, "ECDSA crypto error: {}", str),
+        }
+    }
+}
+
+impl error::Error for Error {
+    fn description(&self) -> &str {
+        "Core error"
+    }
+}
@@ -0,0 +1,196 @@
+//! # Core domain types shared across the crate
+
+mod error;
+
+pub use self::error::Error;
+use secp256k1::Secp256k1;
+use secp256k1::key::{PublicKey, SecretKey};
+use std::fmt;
+use std::str::FromStr;
+use util::{ToHex, keccak256, to_arr, trim_hex};
+
+/// Number of bytes in an `Address`
+pub const ADDRESS_BYTES: usize = 20;
+
+/// Number of bytes in a secp256k1 private key
+pub const PRIVATE_KEY_BYTES: usize = 32;
+
+/// Ethereum/Webchain account address
+#[derive(Clone, Copy, Eq, PartialEq, Hash, Default)]
+pub struct Address([u8; ADDRESS_BYTES]);
+
+impl Address {
+    /// Try to parse an `Address` from a hex string, with or without the `0x` prefix
+    ///
+    /// A mixed-case input is validated against its EIP-55 checksum and
+    /// rejected on mismatch; all-lowercase and all-uppercase input is
+    /// accepted unconditionally.
+    pub fn try_from(s: &str) -> Result<Address, Error> {
+        let hex = trim_hex(s);
+
+        if hex.len() != ADDRESS_BYTES * 2 {
+            return Err(Error::InvalidHexLength(s.to_string()));
+        }
+
+        let mut bytes = [0u8; ADDRESS_BYTES];
+        for (i, b) in bytes.iter_mut().enumerate() {
+            let byte = u8::from_str_radix(&hex[i * 2..i * 2 + 2], 16)
+                .map_err(|_| Error::InvalidHexLength(s.to_string()))?;
+            *b = byte;
+        }
+
+        let addr = Address(bytes);
+
+        let is_mixed_case = hex.chars().any(|c| c.is_ascii_lowercase()) &&
+                             hex.chars().any(|c| c.is_ascii_uppercase());
+
+        if is_mixed_case && addr.to_checksummed() != hex {
+            return Err(Error::InvalidChecksum(s.to_string()));
+        }
+
+        Ok(addr)
+    }
+
+    /// Format this address as an EIP-55 mixed-case checksummed hex string
+    /// (without the `0x` prefix)
+    pub fn to_checksummed(&self) -> String {
+        let hex = self.0.to_hex();
+        let hash = keccak256(hex.as_bytes());
+
+        hex.chars()
+            .enumerate()
+            .map(|(i, ch)| {
+                if ch.is_digit(10) {
+                    return ch;
+                }
+
+                let nibble = if i % 2 == 0 {
+                    hash[i / 2] >> 4
+                } else {
+                    hash[i / 2] & 0x0f
+                };
+
+                if nibble >= 8 {
+                    ch.to_ascii_uppercase()
+                } else {
+                    ch
+                }
+            })
+            .collect()
+    }
+}
+
+impl AsRef<[u8]> for Address {
+    fn as_ref(&self) -> &[u8] {
+        &self.0
+    }
+}
+
+impl From<[u8; ADDRESS_BYTES]> for Address {
+    fn from(bytes: [u8; ADDRESS_BYTES]) -> Self {
+        Address(bytes)
+    }
+}
+
+impl FromStr for Address {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Address::try_from(s)
+    }
+}
+
+impl fmt::Display for Address {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "0x{}", self.0.to_hex())
+    }
+}
+
+impl fmt::Debug for Address {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Display::fmt(self, f)
+    }
+}
+
+/// A secp256k1 private key
+#[derive(Clone, Copy)]
+pub struct PrivateKey(pub [u8; PRIVATE_KEY_BYTES]);
+
+impl PrivateKey {
+    /// Derive the `Address` that corresponds to this private key
+    pub fn to_address(&self) -> Result<Address, Error> {
+        let ctx = Secp256k1::new();
+        let sk = SecretKey::from_slice(&ctx, &self.0).map_err(|e| Error::EcdsaCrypto(e.to_string()))?;
+        let pk = PublicKey::from_secret_key(&ctx, &sk).map_err(|e| Error::EcdsaCrypto(e.to_string()))?;
+
+        // Drop the `0x04` uncompressed-point prefix before hashing
+        let serialized = pk.serialize_vec(&ctx, false);
+        let hash = keccak256(&serialized[1..]);
+
+        Ok(Address(to_arr(&hash[12..])))
+    }
+}
+
+impl From<[u8; PRIVATE_KEY_BYTES]> for PrivateKey {
+    fn from(bytes: [u8; PRIVATE_KEY_BYTES]) -> Self {
+        PrivateKey(bytes)
+    }
+}
+
+impl Into<[u8; PRIVATE_KEY_BYTES]> for PrivateKey {
+    fn into(self) -> [u8; PRIVATE_KEY_BYTES] {
+        self.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Reference vectors from EIP-55
+    const CHECKSUMMED: &'static [&'static str] = &["5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAed",
+                                                    "fB6916095ca1df60bB79Ce92cE3Ea74c37c5d359",
+                                                    "dbF03B407c01E7cD3CBea99509d93f8DDDC8C6FB",
+                                                    "D1220A0cf47c7B9Be7A2E6BA89F429762e7b9aDb"];
+
+    #[test]
+    fn should_checksum_address() {
+        for addr in CHECKSUMMED {
+            let parsed = Address::try_from(&addr.to_lowercase()).unwrap();
+            assert_eq!(parsed.to_checksummed(), *addr);
+        }
+    }
+
+    #[test]
+    fn should_accept_a_correct_checksum() {
+        for addr in CHECKSUMMED {
+            assert!(Address::try_from(addr).is_ok());
+        }
+    }
+
+    #[test]
+    fn should_accept_all_lowercase_or_uppercase() {
+        for addr in CHECKSUMMED {
+            assert!(Address::try_from(&addr.to_lowercase()).is_ok());
+            assert!(Address::try_from(&addr.to_uppercase()).is_ok());
+        }
+    }
+
+    #[test]
+    fn should_reject_a_tampered_checksum() {
+        let mut tampered = CHECKSUMMED[0].to_string();
+        let i = tampered.find(char::is_alphabetic).expect("Expect a letter to tamper with");
+
+        unsafe {
+            let byte = tampered.as_bytes()[i];
+            let flipped = if byte.is_ascii_uppercase() {
+                byte.to_ascii_lowercase()
+            } else {
+                byte.to_ascii_uppercase()
+            };
+            tampered.as_bytes_mut()[i] = flipped;
+        }
+
+        assert!(Address::try_from(&tampered).is_err());
+    }
+}
@@ -0,0 +1,107 @@
+//! # Private key generator
+
+use super::core::{Address, PrivateKey};
+use super::util::keccak256;
+use rand::Rng;
+
+/// Generator of secp256k1 private keys backed by a CSPRNG
+pub struct Generator<R: Rng> {
+    rng: R,
+}
+
+impl<R: Rng> Generator<R> {
+    /// Create a new `Generator` using the given random source
+    pub fn new(rng: R) -> Self {
+        Generator { rng: rng }
+    }
+
+    /// Draw a fresh private key
+    pub fn get(&mut self) -> PrivateKey {
+        let mut buf = [0u8; 32];
+        self.rng.fill_bytes(&mut buf);
+
+        PrivateKey(buf)
+    }
+
+    /// Search for a private key whose derived address starts with `prefix`
+    ///
+    /// Draws up to `max_iterations` candidates from `self` and returns the
+    /// first one whose address bytes match `prefix` byte-for-byte. Returns
+    /// `None` if no match turns up within the iteration budget.
+    pub fn find_prefix(&mut self, prefix: &[u8], max_iterations: usize) -> Option<PrivateKey> {
+        for _ in 0..max_iterations {
+            let pk = self.get();
+
+            if let Ok(addr) = pk.to_address() {
+                if addr.as_ref().starts_with(prefix) {
+                    return Some(pk);
+                }
+            }
+        }
+
+        None
+    }
+}
+
+/// Number of keccak256 rounds applied when deriving a brain-wallet scalar
+const BRAIN_ROUNDS: usize = 16384;
+
+/// secp256k1 group order; a brain-wallet scalar must fall strictly below it
+const SECP256K1_ORDER: [u8; 32] = [0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff,
+                                    0xff, 0xff, 0xff, 0xff, 0xfe, 0xba, 0xae, 0xdc, 0xe6, 0xaf, 0x48,
+                                    0xa0, 0x3b, 0xbf, 0xd2, 0x5e, 0x8c, 0xd0, 0x36, 0x41, 0x41];
+
+/// Deterministic "brain wallet" key derivation from a human passphrase
+///
+/// Mirrors `ethkey`'s `Brain` generator: the passphrase is hashed with
+/// `keccak256` for many rounds to produce a secp256k1 scalar, rehashing
+/// further whenever the candidate is zero or out of range for the curve.
+pub struct Brain(String);
+
+impl Brain {
+    /// Create a brain-wallet generator for `phrase`
+    pub fn new(phrase: String) -> Self {
+        Brain(phrase)
+    }
+
+    /// Derive the private key for this passphrase
+    pub fn generate(&self) -> PrivateKey {
+        let mut digest = keccak256(self.0.as_bytes());
+
+        for _ in 1..BRAIN_ROUNDS {
+            digest = keccak256(&digest);
+        }
+
+        while is_zero(&digest) || digest >= SECP256K1_ORDER {
+            digest = keccak256(&digest);
+        }
+
+        PrivateKey(digest)
+    }
+}
+
+fn is_zero(buf: &[u8; 32]) -> bool {
+    buf.iter().all(|&b| b == 0)
+}
+
+/// Try small variations of `phrase` to recover a private key whose address
+/// matches `target`, mirroring `ethkey`'s `brain_recover`
+pub fn brain_recover(target: &Address, phrase: &str, max_attempts: usize) -> Option<PrivateKey> {
+    for i in 0..max_attempts {
+        let candidate = if i == 0 {
+            phrase.to_string()
+        } else {
+            format!("{} {}", phrase, i)
+        };
+
+        let pk = Brain::new(candidate).generate();
+
+        if let Ok(addr) = pk.to_address() {
+            if addr == *target {
+                return Some(pk);
+            }
+        }
+    }
+
+    None
+}
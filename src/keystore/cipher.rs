@@ -0,0 +1,14 @@
+//! # Symmetric cipher used to encrypt a keyfile's secret
+
+/// Symmetric cipher algorithm
+#[derive(Clone, Copy, Debug, PartialEq, Eq, RustcEncodable, RustcDecodable)]
+pub enum Cipher {
+    /// AES-128 in CTR mode, the only cipher used by the V3 keystore format
+    Aes128Ctr,
+}
+
+impl Default for Cipher {
+    fn default() -> Self {
+        Cipher::Aes128Ctr
+    }
+}
@@ -0,0 +1,55 @@
+//! # Keystore errors
+
+use core;
+use std::{error, fmt, io};
+
+/// Keystore-level errors
+#[derive(Debug)]
+pub enum Error {
+    /// Passphrase did not unlock the keyfile (MAC mismatch)
+    FailedMac,
+
+    /// `cipher_text` is larger than the 32-byte secret it should decrypt to
+    OversizedCipherText(usize),
+
+    /// Problem decoding a JSON keyfile
+    InvalidKeyFile(String),
+
+    /// Underlying I/O failure while reading/writing a keyfile
+    IO(io::Error),
+
+    /// Failure from the `core` module (e.g. deriving an `Address`)
+    Core(core::Error),
+}
+
+impl From<io::Error> for Error {
+    fn from(err: io::Error) -> Self {
+        Error::IO(err)
+    }
+}
+
+impl From<core::Error> for Error {
+    fn from(err: core::Error) -> Self {
+        Error::Core(err)
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Error::FailedMac => write!(f, "Invalid passphrase"),
+            Error::OversizedCipherText(len) => {
+                write!(f, "Cipher text of {} bytes exceeds the 32-byte secret", len)
+            }
+            Error::InvalidKeyFile(ref str) => write!(f, "Invalid keyfile: {}", str),
+            Error::IO(ref err) => write!(f, "Keyfile IO error: {}", err),
+            Error::Core(ref err) => write!(f, "Keyfile core error: {}", err),
+        }
+    }
+}
+
+impl error::Error for Error {
+    fn description(&self) -> &str {
+        "Keystore error"
+    }
+}
@@ -0,0 +1,33 @@
+//! # Key-derivation functions for unlocking a keyfile's secret
+
+use super::prf::Prf;
+
+/// Digest hashed over password and salt by the legacy `EVP_BytesToKey` KDF
+#[derive(Clone, Copy, Debug, PartialEq, Eq, RustcEncodable, RustcDecodable)]
+pub enum EvpDigest {
+    /// MD5, OpenSSL's historical default
+    Md5,
+
+    /// SHA-256
+    Sha256,
+}
+
+/// Key-derivation function and its parameters
+#[derive(Clone, Copy, Debug, PartialEq, RustcEncodable, RustcDecodable)]
+pub enum Kdf {
+    /// `scrypt` with cost `n`, block size `r` and parallelization `p`
+    Scrypt { n: u32, r: u32, p: u32 },
+
+    /// PBKDF2 with pseudo-random function `prf` and `c` iterations
+    Pbkdf2 { prf: Prf, c: u32 },
+
+    /// OpenSSL's legacy `EVP_BytesToKey` (PBKDF1-style) derivation, used by
+    /// keystores that predate scrypt/PBKDF2 support
+    Evp { digest: EvpDigest, count: u32 },
+}
+
+impl Default for Kdf {
+    fn default() -> Self {
+        Kdf::Scrypt { n: 1024, r: 8, p: 1 }
+    }
+}
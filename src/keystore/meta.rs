@@ -0,0 +1,5 @@
+//! # Free-form keyfile metadata (Parity `meta` extension)
+
+/// Marker for the presence of Parity's `meta` keyfile field
+#[derive(Clone, Debug, PartialEq, Eq, RustcEncodable, RustcDecodable)]
+pub struct MetaInfo;
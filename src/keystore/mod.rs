@@ -0,0 +1,356 @@
+//! # `Keystore` files (UTC / JSON keyfiles) management
+
+mod cipher;
+mod error;
+mod kdf;
+pub mod meta;
+
+pub use self::cipher::Cipher;
+pub use self::error::Error;
+pub use self::kdf::{EvpDigest, Kdf};
+pub use self::meta::MetaInfo;
+pub use self::prf::Prf;
+mod prf;
+
+use core::{self, Address, PrivateKey};
+use crypto::aes::{self, KeySize};
+use crypto::digest::Digest;
+use crypto::hmac::Hmac;
+use crypto::md5::Md5;
+use crypto::pbkdf2::pbkdf2;
+use crypto::scrypt::{self, ScryptParams};
+use crypto::sha2::Sha256;
+use crypto::symmetriccipher::SynchronousStreamCipher;
+use rand::{OsRng, Rng};
+use rustc_serialize::json;
+use std::fs::File;
+use std::io::Write;
+use std::path::Path;
+use util::keccak256;
+use uuid::Uuid;
+
+/// Number of bytes in the KDF salt
+pub const KDF_SALT_BYTES: usize = 32;
+
+/// Number of bytes in the keccak256 MAC
+pub const KECCAK256_BYTES: usize = 32;
+
+/// Number of bytes in the AES-CTR initialization vector
+pub const CIPHER_IV_BYTES: usize = 16;
+
+/// How expensive the key-derivation function should be when minting a new keyfile
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SecurityLevel {
+    /// Suitable for tests and throwaway accounts
+    Normal,
+
+    /// Expensive `scrypt` parameters for funds that matter
+    High,
+}
+
+impl SecurityLevel {
+    fn kdf(&self) -> Kdf {
+        match *self {
+            SecurityLevel::Normal => Kdf::Scrypt { n: 1024, r: 8, p: 1 },
+            SecurityLevel::High => Kdf::Scrypt { n: 262_144, r: 8, p: 1 },
+        }
+    }
+}
+
+impl Default for SecurityLevel {
+    fn default() -> Self {
+        SecurityLevel::Normal
+    }
+}
+
+/// A single UTC / JSON keyfile, as used by geth, Parity and this crate
+#[derive(Clone, Debug, PartialEq, RustcEncodable, RustcDecodable)]
+pub struct KeyFile {
+    /// Keyfile identifier
+    pub uuid: Uuid,
+
+    /// Address this keyfile encrypts a private key for, if known
+    pub address: Option<Address>,
+
+    /// Length in bytes of the derived key
+    pub dk_length: u32,
+
+    /// Key-derivation function used to turn a passphrase into a decryption key
+    pub kdf: Kdf,
+
+    /// Salt passed to the `kdf`
+    pub kdf_salt: [u8; KDF_SALT_BYTES],
+
+    /// MAC used to verify the passphrase before decrypting
+    pub keccak256_mac: [u8; KECCAK256_BYTES],
+
+    /// Cipher used to encrypt the private key
+    pub cipher: Cipher,
+
+    /// Encrypted private key
+    pub cipher_text: Vec<u8>,
+
+    /// Initialization vector for `cipher`
+    pub cipher_iv: [u8; CIPHER_IV_BYTES],
+
+    /// Optional human-readable label
+    pub name: Option<String>,
+
+    /// Optional human-readable description
+    pub description: Option<String>,
+
+    /// Whether this account should be listed; hidden accounts are kept out
+    /// of `list_accounts` unless explicitly asked for
+    pub visible: Option<bool>,
+
+    /// Optional Parity-style metadata marker
+    pub meta: Option<MetaInfo>,
+}
+
+impl Default for KeyFile {
+    fn default() -> Self {
+        KeyFile {
+            uuid: Uuid::nil(),
+            address: None,
+            dk_length: 32,
+            kdf: Kdf::default(),
+            kdf_salt: [0u8; KDF_SALT_BYTES],
+            keccak256_mac: [0u8; KECCAK256_BYTES],
+            cipher: Cipher::default(),
+            cipher_text: Vec::new(),
+            cipher_iv: [0u8; CIPHER_IV_BYTES],
+            name: None,
+            description: None,
+            visible: None,
+            meta: None,
+        }
+    }
+}
+
+impl KeyFile {
+    /// Mint a new keyfile protecting a freshly generated private key
+    pub fn new(passwd: &str, sec: &SecurityLevel) -> Result<KeyFile, Error> {
+        let mut rng = OsRng::new().map_err(Error::IO)?;
+
+        let mut secret = [0u8; 32];
+        rng.fill_bytes(&mut secret);
+
+        let mut kdf_salt = [0u8; KDF_SALT_BYTES];
+        rng.fill_bytes(&mut kdf_salt);
+
+        let mut cipher_iv = [0u8; CIPHER_IV_BYTES];
+        rng.fill_bytes(&mut cipher_iv);
+
+        let kdf = sec.kdf();
+        let mut kf = KeyFile {
+            uuid: Uuid::new_v4(),
+            kdf: kdf,
+            kdf_salt: kdf_salt,
+            cipher_iv: cipher_iv,
+            ..KeyFile::default()
+        };
+
+        let derived = kf.derive_key(passwd)?;
+
+        let mut cipher_text = vec![0u8; secret.len()];
+        aes_ctr_xor(&derived[0..16], &cipher_iv, &secret, &mut cipher_text);
+
+        kf.keccak256_mac = mac(&derived[16..32], &cipher_text);
+        kf.cipher_text = cipher_text;
+        kf.address = PrivateKey(secret).to_address().ok();
+
+        Ok(kf)
+    }
+
+    /// Derive the symmetric key used to encrypt/decrypt the private key
+    fn derive_key(&self, passwd: &str) -> Result<[u8; 32], Error> {
+        let mut key = [0u8; 32];
+
+        match self.kdf {
+            Kdf::Scrypt { n, r, p } => {
+                let log_n = (31 - n.max(2).leading_zeros()) as u8;
+                let params = ScryptParams::new(log_n, r, p);
+                scrypt::scrypt(passwd.as_bytes(), &self.kdf_salt, &params, &mut key);
+            }
+            Kdf::Pbkdf2 { c, .. } => {
+                let mut hmac = Hmac::new(Sha256::new(), passwd.as_bytes());
+                pbkdf2(&mut hmac, &self.kdf_salt, c, &mut key);
+            }
+            Kdf::Evp { digest, count } => {
+                evp_bytes_to_key(passwd.as_bytes(), &self.kdf_salt[0..8], digest, count, &mut key);
+            }
+        }
+
+        Ok(key)
+    }
+
+    /// Recover the private key protected by this keyfile
+    ///
+    /// Some third-party geth/Parity exports carry a `cipher_text` shorter
+    /// than the 32-byte secret it decrypts to. The decrypted bytes are
+    /// left-padded into the secret buffer so they land in
+    /// `secret[32 - cipher_text.len()..]`; a `cipher_text` longer than 32
+    /// bytes can't represent a secp256k1 secret and is rejected outright.
+    pub fn extract_key(&self, passwd: &str) -> Result<PrivateKey, Error> {
+        if self.cipher_text.len() > 32 {
+            return Err(Error::OversizedCipherText(self.cipher_text.len()));
+        }
+
+        let derived = self.derive_key(passwd)?;
+
+        if mac(&derived[16..32], &self.cipher_text) != self.keccak256_mac {
+            return Err(Error::FailedMac);
+        }
+
+        let mut plain = vec![0u8; self.cipher_text.len()];
+        aes_ctr_xor(&derived[0..16], &self.cipher_iv, &self.cipher_text, &mut plain);
+
+        let mut secret = [0u8; 32];
+        let offset = 32 - plain.len();
+        secret[offset..].copy_from_slice(&plain);
+
+        Ok(PrivateKey(secret))
+    }
+
+    /// Alias for [`extract_key`](#method.extract_key)
+    pub fn decrypt_key(&self, passwd: &str) -> Result<PrivateKey, Error> {
+        self.extract_key(passwd)
+    }
+
+    /// Recover the `Address` that corresponds to this keyfile's private key
+    pub fn decrypt_address(&self, passwd: &str) -> Result<Address, Error> {
+        self.extract_key(passwd)?.to_address().map_err(Error::from)
+    }
+
+    /// Parse a keyfile from its JSON representation
+    pub fn decode(json: String) -> Result<KeyFile, Error> {
+        json::decode(&json).map_err(|e| Error::InvalidKeyFile(e.to_string()))
+    }
+
+    /// Write this keyfile into `dir`, optionally stamping `address`, `name` and `description`
+    pub fn flush<P: AsRef<Path>>(&self,
+                                  dir: P,
+                                  address: Option<Address>,
+                                  name: Option<String>,
+                                  description: Option<String>)
+                                  -> Result<(), Error> {
+        let mut kf = self.clone();
+
+        if address.is_some() {
+            kf.address = address;
+        }
+        if name.is_some() {
+            kf.name = name;
+        }
+        if description.is_some() {
+            kf.description = description;
+        }
+
+        let path = dir.as_ref().join(kf.uuid.to_string());
+        let mut file = File::create(path)?;
+        let encoded = json::encode(&kf).map_err(|e| Error::InvalidKeyFile(e.to_string()))?;
+        file.write_all(encoded.as_bytes())?;
+
+        Ok(())
+    }
+}
+
+fn mac(derived_right: &[u8], cipher_text: &[u8]) -> [u8; KECCAK256_BYTES] {
+    let mut buf = Vec::with_capacity(derived_right.len() + cipher_text.len());
+    buf.extend_from_slice(derived_right);
+    buf.extend_from_slice(cipher_text);
+
+    keccak256(&buf)
+}
+
+fn aes_ctr_xor(key: &[u8], iv: &[u8], input: &[u8], output: &mut [u8]) {
+    let mut ctr = aes::ctr(KeySize::KeySize128, key, iv);
+    ctr.process(input, output);
+}
+
+/// OpenSSL's legacy `EVP_BytesToKey`: `D_1 = H(P||salt)`,
+/// `D_{i+1} = H(D_i||P||salt)`, each iterated `count` times, concatenated
+/// until `out` is full
+fn evp_bytes_to_key(passwd: &[u8], salt: &[u8], digest: EvpDigest, count: u32, out: &mut [u8]) {
+    let mut filled = 0;
+    let mut prev: Vec<u8> = Vec::new();
+
+    while filled < out.len() {
+        let mut block = prev.clone();
+        block.extend_from_slice(passwd);
+        block.extend_from_slice(salt);
+        block = evp_digest(digest, &block);
+
+        for _ in 1..count {
+            block = evp_digest(digest, &block);
+        }
+
+        let take = ::std::cmp::min(block.len(), out.len() - filled);
+        out[filled..filled + take].copy_from_slice(&block[..take]);
+        filled += take;
+        prev = block;
+    }
+}
+
+fn evp_digest(digest: EvpDigest, data: &[u8]) -> Vec<u8> {
+    match digest {
+        EvpDigest::Md5 => {
+            let mut md5 = Md5::new();
+            md5.input(data);
+
+            let mut out = vec![0u8; md5.output_bytes()];
+            md5.result(&mut out);
+            out
+        }
+        EvpDigest::Sha256 => {
+            let mut sha256 = Sha256::new();
+            sha256.input(data);
+
+            let mut out = vec![0u8; sha256.output_bytes()];
+            sha256.result(&mut out);
+            out
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rustc_serialize::hex::FromHex;
+    use util::to_arr;
+
+    /// A hand-built PBKDF2 keyfile whose `cipher_text` is 31 bytes (one
+    /// short of the 32-byte secret), as produced by some third-party
+    /// exports that drop a leading zero byte
+    #[test]
+    fn should_extract_key_from_short_ciphertext() {
+        let kf = KeyFile {
+            dk_length: 32,
+            kdf: Kdf::Pbkdf2 {
+                prf: Prf::default(),
+                c: 10240,
+            },
+            kdf_salt: to_arr(&"0102030405060708090a0b0c0d0e0f101112131415161718191a1b1c1d1e1f"
+                                   .from_hex()
+                                   .unwrap()),
+            keccak256_mac: to_arr(&"5d20acc6f2d66b514d3a966a3375082ce3f1e3908cbe69c2be1e1d6e4f981e30"
+                                        .from_hex()
+                                        .unwrap()),
+            cipher_text: "b5fcd3539bcf3c8896471547e4c4796c4a13e80a0c6f23cff5ae841056802c"
+                .from_hex()
+                .unwrap(),
+            cipher_iv: to_arr(&"000102030405060708090a0b0c0d0e0f".from_hex().unwrap()),
+            ..KeyFile::default()
+        };
+
+        assert_eq!(kf.cipher_text.len(), 31);
+
+        let pkey: [u8; 32] = kf.extract_key("testpassword").unwrap().into();
+        assert_eq!(pkey.to_vec(),
+                   "0011223344556677889900aabbccddeeff0123456789abcdef0123456789abcd"
+                       .from_hex()
+                       .unwrap());
+
+        assert!(kf.extract_key("wrongpassword").is_err());
+    }
+}
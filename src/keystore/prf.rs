@@ -0,0 +1,14 @@
+//! # Pseudo-random function used by PBKDF2
+
+/// PBKDF2 pseudo-random function
+#[derive(Clone, Copy, Debug, PartialEq, Eq, RustcEncodable, RustcDecodable)]
+pub enum Prf {
+    /// HMAC-SHA256, the only PRF used by the V3 keystore format
+    HmacSha256,
+}
+
+impl Default for Prf {
+    fn default() -> Self {
+        Prf::HmacSha256
+    }
+}
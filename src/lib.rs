@@ -0,0 +1,26 @@
+//! # Webchain connector library
+
+extern crate byteorder;
+extern crate crypto;
+extern crate futures;
+extern crate jsonrpc_core;
+extern crate jsonrpc_http_server;
+extern crate jsonrpc_ipc_server;
+#[macro_use]
+extern crate log;
+extern crate rand;
+extern crate rustc_serialize;
+extern crate secp256k1;
+extern crate serde_json;
+extern crate uuid;
+
+pub mod core;
+pub mod key_generator;
+pub mod keystore;
+pub mod rpc;
+pub mod transaction;
+pub mod util;
+
+pub use self::core::{Address, PrivateKey};
+pub use self::keystore::KeyFile;
+pub use self::transaction::{Transaction, sign_transaction};
@@ -0,0 +1,86 @@
+//! # Typed RPC error mapping
+//!
+//! Maps the crate's own error types onto distinct `jsonrpc_core::Error`
+//! values with stable custom codes, so clients can tell "account not
+//! found" apart from "bad passphrase" apart from "storage I/O error"
+//! instead of everything collapsing into `internal_error()`.
+
+use jsonrpc_core::{Error as JsonRpcError, ErrorCode};
+use serde_json::Value;
+use super::super::keystore;
+use super::super::storage;
+
+/// Stable custom error codes, in the `-32000..-32099` JSON-RPC server-error range
+mod codes {
+    /// No keyfile found for the requested address
+    pub const ACCOUNT_NOT_FOUND: i64 = -32010;
+
+    /// Passphrase did not decrypt the keyfile
+    pub const INVALID_PASSPHRASE: i64 = -32011;
+
+    /// Keyfile could not be parsed
+    pub const INVALID_KEYFILE: i64 = -32012;
+
+    /// Underlying storage/filesystem failure, or any other failure without a
+    /// code of its own
+    pub const STORAGE_ERROR: i64 = -32013;
+}
+
+/// Crate-level errors surfaced to RPC clients
+#[derive(Debug)]
+pub enum Error {
+    /// No keyfile found for `address`
+    AccountNotFound(String),
+
+    /// Passphrase did not decrypt the keyfile
+    InvalidPassphrase,
+
+    /// Keyfile could not be parsed
+    InvalidKeyFile(String),
+
+    /// Underlying storage/filesystem failure, or any other failure without a
+    /// variant of its own (e.g. signing or contract errors)
+    StorageError(String),
+}
+
+impl From<keystore::Error> for Error {
+    fn from(err: keystore::Error) -> Self {
+        match err {
+            keystore::Error::FailedMac => Error::InvalidPassphrase,
+            keystore::Error::InvalidKeyFile(s) => Error::InvalidKeyFile(s),
+            other => Error::StorageError(other.to_string()),
+        }
+    }
+}
+
+impl From<storage::Error> for Error {
+    fn from(err: storage::Error) -> Self {
+        match err {
+            storage::Error::NotFound(addr) => Error::AccountNotFound(addr),
+            other => Error::StorageError(other.to_string()),
+        }
+    }
+}
+
+impl From<Error> for JsonRpcError {
+    fn from(err: Error) -> Self {
+        let (code, message, data) = match err {
+            Error::AccountNotFound(addr) => {
+                (codes::ACCOUNT_NOT_FOUND, "Account not found", Some(Value::String(addr)))
+            }
+            Error::InvalidPassphrase => (codes::INVALID_PASSPHRASE, "Invalid passphrase", None),
+            Error::InvalidKeyFile(details) => {
+                (codes::INVALID_KEYFILE, "Invalid keyfile", Some(Value::String(details)))
+            }
+            Error::StorageError(details) => {
+                (codes::STORAGE_ERROR, "Storage error", Some(Value::String(details)))
+            }
+        };
+
+        JsonRpcError {
+            code: ErrorCode::ServerError(code),
+            message: message.to_string(),
+            data: data,
+        }
+    }
+}
@@ -6,20 +6,24 @@ mod error;
 
 pub use self::error::Error;
 use super::contract::Contracts;
-use super::core::{self, Address, Transaction};
+use super::core::{self, Address};
 use super::keystore::{KeyFile, SecurityLevel};
+use super::transaction::Transaction;
 use super::storage::{ChainStorage, Storages, default_path};
+use super::storage::keyfile::{DbStorage, KeyfileStorage};
 use super::util::{ToHex, align_bytes, to_arr, to_u64, trim_hex};
 use futures;
 use jsonrpc_core::{Error as JsonRpcError, ErrorCode, IoHandler, Params};
 use jsonrpc_core::futures::Future;
-use jsonrpc_minihttp_server::{DomainsValidation, ServerBuilder, cors};
+use jsonrpc_http_server::{DomainsValidation, ServerBuilder, cors};
+use jsonrpc_ipc_server::ServerBuilder as IpcServerBuilder;
 use log::LogLevel;
 use rustc_serialize::json;
-use serde_json::Value;
+use serde_json::{self, Value};
 use std::net::SocketAddr;
 use std::path::PathBuf;
 use std::sync::Arc;
+use std::time::Duration;
 
 /// RPC methods
 #[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
@@ -62,14 +66,87 @@ pub enum ClientMethod {
 #[derive(Clone, Debug, PartialEq)]
 pub struct MethodParams<'a>(pub ClientMethod, pub &'a Params);
 
-/// Start an HTTP RPC endpoint
-pub fn start(addr: &SocketAddr,
-             client_addr: &SocketAddr,
-             base_path: Option<PathBuf>,
-             sec_level: SecurityLevel)
-{
+/// Tunable knobs for the HTTP RPC transport
+#[derive(Clone, Copy, Debug)]
+pub struct HttpConfig {
+    /// Number of request-processing worker threads
+    pub threads: usize,
+
+    /// Whether to keep idle client connections open between requests
+    pub keep_alive: bool,
+
+    /// How long to wait on an upstream proxy call to `client_addr` before
+    /// aborting it and returning an error to the caller
+    pub timeout: Duration,
+}
+
+impl Default for HttpConfig {
+    fn default() -> Self {
+        HttpConfig {
+            threads: 4,
+            keep_alive: true,
+            timeout: Duration::from_secs(60),
+        }
+    }
+}
+
+/// CORS / `Host`-header access policy for the HTTP transport
+#[derive(Clone, Debug)]
+pub struct AccessPolicy {
+    /// Allowed CORS origins
+    pub cors_origins: Vec<cors::AccessControlAllowOrigin>,
+
+    /// Allowed `Host` header values
+    pub allowed_hosts: Vec<String>,
+}
+
+impl Default for AccessPolicy {
+    /// A safe, localhost-only policy
+    fn default() -> Self {
+        AccessPolicy {
+            cors_origins: vec![cors::AccessControlAllowOrigin::Value("http://localhost".to_string())],
+            allowed_hosts: vec!["localhost".to_string(), "127.0.0.1".to_string()],
+        }
+    }
+}
+
+/// Build the `IoHandler` shared by every RPC transport (HTTP, IPC, ...)
+///
+/// Every method is registered exactly once here so the HTTP and IPC
+/// surfaces stay identical by construction. `chain_id` is the EIP-155 id of
+/// the network `client_addr` connects to, used to sign `eth_sendTransaction`
+/// requests. `timeout` bounds how long each method waits on its proxied
+/// call to `client_addr`.
+pub fn build_handler(client_addr: &SocketAddr,
+                      base_path: Option<PathBuf>,
+                      chain_id: u64,
+                      sec_level: SecurityLevel,
+                      timeout: Duration)
+                      -> IoHandler {
     let mut io = IoHandler::default();
-    let url = Arc::new(http::AsyncWrapper::new(&format!("http://{}", client_addr)));
+    let url = Arc::new(http::AsyncWrapper::new(&format!("http://{}", client_addr), timeout));
+
+    let storage = match base_path {
+        Some(p) => Storages::new(p),
+        None => Storages::default(),
+    };
+
+    if storage.init().is_err() {
+        panic!("Unable to initialize storage");
+    }
+
+    let chain = ChainStorage::new(&storage, "default".to_string());
+
+    if chain.init().is_err() {
+        panic!("Unable to initialize chain");
+    }
+
+    let keystore_dir = chain
+        .get_path("keystore".to_string())
+        .expect("Expect directory for keystore");
+
+    let keyfile_storage: Arc<KeyfileStorage> =
+        Arc::new(DbStorage::new(keystore_dir).expect("Expect to open keystore database"));
 
     {
         let url = url.clone();
@@ -122,13 +199,35 @@ pub fn start(addr: &SocketAddr,
 
     {
         let url = url.clone();
+        let keyfile_storage = keyfile_storage.clone();
 
         let callback = move |p| {
-            let pk = KeyFile::default().decrypt_key("");
+            let (from, passphrase) = match sender_credentials(&p) {
+                Ok(pair) => pair,
+                Err(err) => return futures::done(Err(err)).boxed(),
+            };
+
+            let kf = match keyfile_storage.search_by_address(&from) {
+                Ok(kf) => kf,
+                Err(e) => return futures::done(Err(JsonRpcError::from(Error::from(e)))).boxed(),
+            };
+
+            let pk = match kf.decrypt_key(&passphrase) {
+                Ok(pk) => pk,
+                Err(e) => return futures::done(Err(JsonRpcError::from(Error::from(e)))).boxed(),
+            };
+
             match Transaction::try_from(&p) {
                 Ok(tr) => {
-                    url.request(&MethodParams(ClientMethod::EthSendRawTransaction,
-                                              &tr.to_raw_params(pk.unwrap())))
+                    match tr.to_raw_params(pk, chain_id) {
+                        Ok(params) => {
+                            url.request(&MethodParams(ClientMethod::EthSendRawTransaction, &params))
+                        }
+                        Err(e) => {
+                            futures::done(Err(JsonRpcError::from(Error::StorageError(e.to_string()))))
+                                .boxed()
+                        }
+                    }
                 }
                 Err(err) => {
                     futures::done(Err(JsonRpcError::invalid_params(err.to_string()))).boxed()
@@ -182,7 +281,7 @@ pub fn start(addr: &SocketAddr,
                         let addr = Address::default().to_string();
                         match kf.flush(&default_path(), None, name, descr) {
                             Ok(_) => futures::done(Ok(Value::String(addr))).boxed(),
-                            Err(_) => futures::done(Err(JsonRpcError::internal_error())).boxed(),
+                            Err(e) => futures::done(Err(JsonRpcError::from(Error::from(e)))).boxed(),
                         }
                     }
                     Err(_) => {
@@ -206,15 +305,16 @@ pub fn start(addr: &SocketAddr,
 
                 match KeyFile::new(passwd, &sec) {
                     Ok(kf) => {
-                        let addr_res = kf.decrypt_address(passwd);
-                        if addr_res.is_err() {
-                            return futures::done(Err(JsonRpcError::internal_error())).boxed();
-                        }
-                        let addr = addr_res.unwrap();
+                        let addr = match kf.decrypt_address(passwd) {
+                            Ok(addr) => addr,
+                            Err(e) => {
+                                return futures::done(Err(JsonRpcError::from(Error::from(e)))).boxed()
+                            }
+                        };
 
                         match kf.flush(&default_path(), Some(addr), None, None) {
                             Ok(_) => futures::done(Ok(Value::String(addr.to_string()))).boxed(),
-                            Err(_) => futures::done(Err(JsonRpcError::internal_error())).boxed(),
+                            Err(e) => futures::done(Err(JsonRpcError::from(Error::from(e)))).boxed(),
                         }
                     }
                     Err(_) => {
@@ -233,21 +333,6 @@ pub fn start(addr: &SocketAddr,
         io.add_async_method("personal_newAccount", create_callback);
     }
 
-    let storage = match base_path {
-        Some(p) => Storages::new(p),
-        None => Storages::default(),
-    };
-
-    if storage.init().is_err() {
-        panic!("Unable to initialize storage");
-    }
-
-    let chain = ChainStorage::new(&storage, "default".to_string());
-
-    if chain.init().is_err() {
-        panic!("Unable to initialize chain");
-    }
-
     let dir = chain
         .get_path("contracts".to_string())
         .expect("Expect directory for contracts");
@@ -268,16 +353,152 @@ pub fn start(addr: &SocketAddr,
             Params::Array(ref vec) => {
                 match contracts.add(&vec[0]) {
                     Ok(_) => futures::finished(Value::Bool(true)).boxed(),
-                    Err(_) => futures::failed(JsonRpcError::new(ErrorCode::InternalError)).boxed(),
+                    Err(e) => {
+                        let err = Error::StorageError(e.to_string());
+                        futures::failed(JsonRpcError::from(err)).boxed()
+                    }
                 }
             }
             _ => futures::failed(JsonRpcError::new(ErrorCode::InvalidParams)).boxed(),
         });
     }
 
+    {
+        let keyfile_storage = keyfile_storage.clone();
+
+        io.add_async_method("emerald_listAccounts", move |p| {
+            let show_hidden = Params::parse::<Value>(p)
+                .ok()
+                .and_then(|v| v.as_array().and_then(|arr| arr.get(0)).and_then(Value::as_bool))
+                .unwrap_or(false);
+
+            match keyfile_storage.list_accounts(show_hidden) {
+                Ok(accounts) => {
+                    let list = accounts
+                        .iter()
+                        .map(|a| serde_json::to_value(a).unwrap_or(Value::Null))
+                        .collect();
+                    futures::finished(Value::Array(list)).boxed()
+                }
+                Err(e) => futures::failed(JsonRpcError::from(Error::from(e))).boxed(),
+            }
+        });
+    }
+
+    {
+        let keyfile_storage = keyfile_storage.clone();
+
+        io.add_async_method("emerald_hideAccount", move |p| match account_address(p) {
+            Ok(addr) => {
+                match keyfile_storage.hide(&addr) {
+                    Ok(res) => futures::finished(Value::Bool(res)).boxed(),
+                    Err(e) => futures::failed(JsonRpcError::from(Error::from(e))).boxed(),
+                }
+            }
+            Err(err) => futures::failed(err).boxed(),
+        });
+    }
+
+    {
+        let keyfile_storage = keyfile_storage.clone();
+
+        io.add_async_method("emerald_unhideAccount", move |p| match account_address(p) {
+            Ok(addr) => {
+                match keyfile_storage.unhide(&addr) {
+                    Ok(res) => futures::finished(Value::Bool(res)).boxed(),
+                    Err(e) => futures::failed(JsonRpcError::from(Error::from(e))).boxed(),
+                }
+            }
+            Err(err) => futures::failed(err).boxed(),
+        });
+    }
+
+    {
+        let keyfile_storage = keyfile_storage.clone();
+
+        io.add_async_method("emerald_updateAccount", move |p| match Params::parse::<Value>(p) {
+            Ok(ref v) => {
+                let data = match v.as_object() {
+                    Some(data) => data,
+                    None => return futures::failed(JsonRpcError::invalid_params("Invalid JSON object")).boxed(),
+                };
+
+                let addr = match data.get("address").and_then(Value::as_str).and_then(|s| s.parse().ok()) {
+                    Some(addr) => addr,
+                    None => {
+                        return futures::failed(JsonRpcError::invalid_params("Invalid address")).boxed()
+                    }
+                };
+
+                let name = data.get("name").and_then(Value::as_str).map(str::to_string);
+                let description = data.get("description").and_then(Value::as_str).map(str::to_string);
+
+                match keyfile_storage.update(&addr, name, description) {
+                    Ok(_) => futures::finished(Value::Bool(true)).boxed(),
+                    Err(e) => futures::failed(JsonRpcError::from(Error::from(e))).boxed(),
+                }
+            }
+            Err(_) => futures::failed(JsonRpcError::invalid_params("Invalid JSON object")).boxed(),
+        });
+    }
+
+    io
+}
+
+/// Parse the single `Address` argument shared by the `emerald_hideAccount` /
+/// `emerald_unhideAccount` methods
+fn account_address(p: Params) -> Result<Address, JsonRpcError> {
+    Params::parse::<Value>(p)
+        .ok()
+        .and_then(|v| v.as_array().and_then(|arr| arr.get(0)).and_then(Value::as_str).map(str::to_string))
+        .and_then(|s| s.parse().ok())
+        .ok_or_else(|| JsonRpcError::invalid_params("Invalid address"))
+}
+
+/// Pull the sender `from` address and unlocking `passphrase` out of the
+/// `eth_sendTransaction` params object, so the handler can look up and
+/// decrypt the matching keyfile instead of signing with a stand-in key
+fn sender_credentials(p: &Params) -> Result<(Address, String), JsonRpcError> {
+    let value: Value = p.clone()
+        .parse()
+        .map_err(|_| JsonRpcError::invalid_params("Invalid JSON object"))?;
+
+    let obj = value
+        .as_array()
+        .and_then(|arr| arr.get(0))
+        .and_then(Value::as_object)
+        .ok_or_else(|| JsonRpcError::invalid_params("Expected a transaction object"))?;
+
+    let from = obj.get("from")
+        .and_then(Value::as_str)
+        .ok_or_else(|| JsonRpcError::invalid_params("Missing \"from\" address"))?
+        .parse()
+        .map_err(|_| JsonRpcError::invalid_params("Invalid \"from\" address"))?;
+
+    let passphrase = obj.get("passphrase")
+        .and_then(Value::as_str)
+        .ok_or_else(|| JsonRpcError::invalid_params("Missing \"passphrase\""))?
+        .to_string();
+
+    Ok((from, passphrase))
+}
+
+/// Start a threaded HTTP RPC endpoint
+pub fn start(addr: &SocketAddr,
+             client_addr: &SocketAddr,
+             base_path: Option<PathBuf>,
+             chain_id: u64,
+             sec_level: SecurityLevel,
+             http_cfg: &HttpConfig,
+             access: &AccessPolicy)
+{
+    let io = build_handler(client_addr, base_path, chain_id, sec_level, http_cfg.timeout);
+
     let server = ServerBuilder::new(io)
-        .cors(DomainsValidation::AllowOnly(vec![cors::AccessControlAllowOrigin::Any,
-                                                cors::AccessControlAllowOrigin::Null]))
+        .cors(DomainsValidation::AllowOnly(access.cors_origins.clone()))
+        .allowed_hosts(DomainsValidation::AllowOnly(access.allowed_hosts.clone()))
+        .threads(http_cfg.threads)
+        .keep_alive(http_cfg.keep_alive)
         .start_http(addr)
         .expect("Expect to build HTTP RPC server");
 
@@ -285,5 +506,29 @@ pub fn start(addr: &SocketAddr,
         info!("Connector started on http://{}", server.address());
     }
 
-    server.wait().expect("Expect to start HTTP RPC server");
+    server.wait();
+}
+
+/// Start a local IPC RPC endpoint (Unix domain socket, or named pipe on Windows)
+///
+/// Serves the exact same `IoHandler` as [`start`](fn.start.html), so local
+/// tooling gets an identical method surface without opening a TCP port.
+pub fn start_ipc(path: &str,
+                  client_addr: &SocketAddr,
+                  base_path: Option<PathBuf>,
+                  chain_id: u64,
+                  sec_level: SecurityLevel,
+                  timeout: Duration)
+{
+    let io = build_handler(client_addr, base_path, chain_id, sec_level, timeout);
+
+    let server = IpcServerBuilder::new(io)
+        .start(path)
+        .expect("Expect to build IPC RPC server");
+
+    if log_enabled!(LogLevel::Info) {
+        info!("Connector started on ipc://{}", path);
+    }
+
+    server.wait();
 }
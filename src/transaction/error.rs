@@ -0,0 +1,35 @@
+//! # Transaction parsing/signing errors
+
+use super::super::core;
+use std::{error, fmt};
+
+/// Transaction-level errors
+#[derive(Debug)]
+pub enum Error {
+    /// `Params` didn't decode into the shape a transaction expects
+    InvalidParams(String),
+
+    /// Failure from the `core` module (e.g. an invalid `Address`)
+    Core(core::Error),
+}
+
+impl From<core::Error> for Error {
+    fn from(err: core::Error) -> Self {
+        Error::Core(err)
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Error::InvalidParams(ref str) => write!(f, "Invalid transaction params: {}", str),
+            Error::Core(ref err) => write!(f, "Transaction core error: {}", err),
+        }
+    }
+}
+
+impl error::Error for Error {
+    fn description(&self) -> &str {
+        "Transaction error"
+    }
+}
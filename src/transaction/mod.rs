@@ -0,0 +1,266 @@
+//! # Transaction signing, built on the crate's RLP + keccak256 utilities
+
+mod error;
+
+pub use self::error::Error;
+use super::core::{self, Address, PrivateKey};
+use super::util::{KECCAK256_BYTES, RLPList, ToHex, WriteRLP, keccak256, to_u64, trim_bytes, trim_hex};
+use jsonrpc_core::Params;
+use rustc_serialize::hex::FromHex;
+use secp256k1::key::SecretKey;
+use secp256k1::{Message, Secp256k1};
+use serde_json::Value;
+
+/// An Ethereum/Webchain transaction ready to be signed
+#[derive(Clone, Debug, Default)]
+pub struct Transaction {
+    /// Sequential number issued by the sender account
+    pub nonce: u64,
+
+    /// Gas price, in wei
+    pub gas_price: [u8; 32],
+
+    /// Gas limit
+    pub gas_limit: u64,
+
+    /// Recipient; `None` marks a contract-creation transaction
+    pub to: Option<Address>,
+
+    /// Value to transfer, in wei
+    pub value: [u8; 32],
+
+    /// Call or contract-init data
+    pub data: Vec<u8>,
+}
+
+impl Transaction {
+    /// Parse a `Transaction` out of the single-object `Params` sent for
+    /// `eth_sendTransaction`
+    pub fn try_from(p: &Params) -> Result<Transaction, Error> {
+        let value: Value = p.clone().parse().map_err(|e| Error::InvalidParams(e.to_string()))?;
+
+        let obj = value
+            .as_array()
+            .and_then(|arr| arr.get(0))
+            .and_then(Value::as_object)
+            .ok_or_else(|| Error::InvalidParams("Expected a transaction object".to_string()))?;
+
+        let to = match obj.get("to").and_then(Value::as_str) {
+            Some(s) => Some(Address::try_from(s)?),
+            None => None,
+        };
+
+        Ok(Transaction {
+               nonce: obj.get("nonce").and_then(Value::as_str).map_or(0, parse_u64),
+               gas_price: obj.get("gasPrice").and_then(Value::as_str).map_or([0u8; 32], parse_u256),
+               gas_limit: obj.get("gas").and_then(Value::as_str).map_or(21000, parse_u64),
+               to: to,
+               value: obj.get("value").and_then(Value::as_str).map_or([0u8; 32], parse_u256),
+               data: obj.get("data")
+                   .and_then(Value::as_str)
+                   .and_then(|s| trim_hex(s).from_hex().ok())
+                   .unwrap_or_default(),
+           })
+    }
+
+    /// RLP list of the six base fields, shared by the signing hash and the
+    /// final signed payload
+    fn base_rlp(&self) -> RLPList {
+        let mut list = RLPList::default();
+
+        list.add_item(&self.nonce);
+        list.add_item(&trim_bytes(&self.gas_price));
+        list.add_item(&self.gas_limit);
+
+        match self.to {
+            Some(ref addr) => list.add_item(&addr.as_ref()),
+            None => list.add_item(&""),
+        }
+
+        list.add_item(&trim_bytes(&self.value));
+        list.add_item(&self.data);
+
+        list
+    }
+
+    /// keccak256 of the six base fields plus `(chain_id, 0, 0)`, per EIP-155
+    fn signing_hash(&self, chain_id: u64) -> [u8; KECCAK256_BYTES] {
+        let mut list = self.base_rlp();
+
+        list.add_item(&chain_id);
+        list.add_item(&"");
+        list.add_item(&"");
+
+        let mut buf = Vec::new();
+        list.write_rlp(&mut buf);
+
+        keccak256(&buf)
+    }
+
+    /// Sign this transaction with `pk` and RLP-encode all nine fields,
+    /// ready for `eth_sendRawTransaction`
+    pub fn to_signed_raw(&self, pk: PrivateKey, chain_id: u64) -> Result<Vec<u8>, core::Error> {
+        let hash = self.signing_hash(chain_id);
+
+        let ctx = Secp256k1::new();
+        let sk = SecretKey::from_slice(&ctx, &pk.0).map_err(|e| core::Error::EcdsaCrypto(e.to_string()))?;
+        let msg = Message::from_slice(&hash).map_err(|e| core::Error::EcdsaCrypto(e.to_string()))?;
+        let sig = ctx.sign_recoverable(&msg, &sk).map_err(|e| core::Error::EcdsaCrypto(e.to_string()))?;
+
+        let (rec_id, data) = sig.serialize_compact(&ctx);
+        let r = trim_bytes(&data[0..32]);
+        let s = trim_bytes(&data[32..64]);
+        let v = u64::from(rec_id.to_i32() as u8) + chain_id * 2 + 35;
+
+        let mut list = self.base_rlp();
+        list.add_item(&v);
+        list.add_item(&r);
+        list.add_item(&s);
+
+        let mut buf = Vec::new();
+        list.write_rlp(&mut buf);
+
+        Ok(buf)
+    }
+
+    /// Sign this transaction for `chain_id` and wrap the raw payload as the
+    /// `Params` expected by `eth_sendRawTransaction`
+    pub fn to_raw_params(&self, pk: PrivateKey, chain_id: u64) -> Result<Params, core::Error> {
+        let raw = self.to_signed_raw(pk, chain_id)?;
+
+        Ok(Params::Array(vec![Value::String(format!("0x{}", raw.to_hex()))]))
+    }
+}
+
+/// Sign `tx` for `chain_id` with `pk`, returning the raw RLP bytes of the
+/// signed transaction
+pub fn sign_transaction(tx: &Transaction, chain_id: u64, pk: PrivateKey) -> Result<Vec<u8>, core::Error> {
+    tx.to_signed_raw(pk, chain_id)
+}
+
+/// Parse a `u64`, discarding input longer than 8 bytes rather than
+/// underflowing in [`util::align_bytes`](../util/fn.align_bytes.html)
+fn parse_u64(hex: &str) -> u64 {
+    let bytes = trim_hex(hex).from_hex().unwrap_or_default();
+
+    if bytes.len() > 8 {
+        return 0;
+    }
+
+    to_u64(&bytes)
+}
+
+fn parse_u256(hex: &str) -> [u8; 32] {
+    let bytes = trim_hex(hex).from_hex().unwrap_or_default();
+    let mut out = [0u8; 32];
+
+    if bytes.len() <= 32 {
+        let offset = 32 - bytes.len();
+        out[offset..].copy_from_slice(&bytes);
+    }
+
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use secp256k1::{RecoverableSignature, RecoveryId};
+    use util::to_arr;
+
+    /// Read a single RLP byte-string item starting at `buf[0]`, returning
+    /// its content and the number of bytes it consumed
+    fn rlp_item(buf: &[u8]) -> (Vec<u8>, usize) {
+        let head = buf[0];
+
+        if head < 0x80 {
+            (vec![head], 1)
+        } else if head <= 0xb7 {
+            let len = (head - 0x80) as usize;
+            (buf[1..1 + len].to_vec(), 1 + len)
+        } else {
+            let len_of_len = (head - 0xb7) as usize;
+            let mut len = 0usize;
+            for b in &buf[1..1 + len_of_len] {
+                len = (len << 8) | (*b as usize);
+            }
+            let start = 1 + len_of_len;
+            (buf[start..start + len].to_vec(), start + len)
+        }
+    }
+
+    /// Decode the top-level items of an RLP list (no nested lists), enough
+    /// to inspect a signed transaction's nine fields
+    fn rlp_list_items(buf: &[u8]) -> Vec<Vec<u8>> {
+        let head = buf[0] as usize;
+
+        let payload = if head <= 0xf7 {
+            &buf[1..1 + (head - 0xc0)]
+        } else {
+            let len_of_len = head - 0xf7;
+            let mut len = 0usize;
+            for b in &buf[1..1 + len_of_len] {
+                len = (len << 8) | (*b as usize);
+            }
+            let start = 1 + len_of_len;
+            &buf[start..start + len]
+        };
+
+        let mut items = Vec::new();
+        let mut pos = 0;
+        while pos < payload.len() {
+            let (item, consumed) = rlp_item(&payload[pos..]);
+            items.push(item);
+            pos += consumed;
+        }
+
+        items
+    }
+
+    /// Known nonce/gasPrice/value/chain-id vector, signed with a fixed
+    /// private key: `gasPrice`/`value` are zero, so a correct encoder must
+    /// emit them as the empty RLP string (`0x80`), not 32 zero bytes; the
+    /// recovered signer must match the signing key's own address
+    #[test]
+    fn should_sign_and_round_trip_a_known_transaction() {
+        let pk = PrivateKey([0x11u8; 32]);
+        let chain_id = 61u64;
+
+        let tx = Transaction {
+            nonce: 9,
+            gas_price: [0u8; 32],
+            gas_limit: 21000,
+            to: Some(Address::try_from(&"35".repeat(20)).unwrap()),
+            value: [0u8; 32],
+            data: Vec::new(),
+        };
+
+        let raw = tx.to_signed_raw(pk, chain_id).unwrap();
+        let items = rlp_list_items(&raw);
+
+        assert_eq!(items.len(), 9);
+        assert_eq!(items[0], vec![9]);
+        assert_eq!(items[1], Vec::<u8>::new(), "zero gasPrice must encode as the empty string");
+        assert_eq!(items[3], tx.to.unwrap().as_ref().to_vec());
+        assert_eq!(items[4], Vec::<u8>::new(), "zero value must encode as the empty string");
+        assert_eq!(items[5], Vec::<u8>::new());
+
+        let v = to_u64(&items[6]);
+        let rec_id = RecoveryId::from_i32((v - 35 - 2 * chain_id) as i32).unwrap();
+
+        let mut sig = [0u8; 64];
+        sig[32 - items[7].len()..32].copy_from_slice(&items[7]);
+        sig[64 - items[8].len()..64].copy_from_slice(&items[8]);
+
+        let ctx = Secp256k1::new();
+        let recoverable = RecoverableSignature::from_compact(&ctx, &sig, rec_id).unwrap();
+        let msg = Message::from_slice(&tx.signing_hash(chain_id)).unwrap();
+        let recovered_pubkey = ctx.recover(&msg, &recoverable).unwrap();
+
+        let serialized = recovered_pubkey.serialize_vec(&ctx, false);
+        let addr_bytes: [u8; 20] = to_arr(&keccak256(&serialized[1..])[12..]);
+        let recovered_addr = Address::from(addr_bytes);
+
+        assert_eq!(recovered_addr, pk.to_address().unwrap());
+    }
+}
@@ -85,6 +85,8 @@ fn should_work_with_keyfile_with_address() {
         cipher_iv: arr!(&"9df1649dd1c50f2153917e3b9e7164e9".from_hex().unwrap(),
                         CIPHER_IV_BYTES),
         name: None,
+        description: None,
+        visible: None,
         meta: None,
     };
 
@@ -131,6 +133,8 @@ fn should_work_with_keyfile_without_address() {
         cipher_iv: arr!(&"58d54158c3e27131b0a0f2b91201aedc".from_hex().unwrap(),
                         CIPHER_IV_BYTES),
         name: None,
+        description: None,
+        visible: None,
         meta: None,
     };
 
@@ -227,6 +231,8 @@ fn should_import_from_geth() {
         cipher_iv: arr!(&"9b9bbcfcf8efc6ca67bd5ecb6edc22d7".from_hex().unwrap(),
                         CIPHER_IV_BYTES),
         name: None,
+        description: None,
+        visible: None,
         meta: None,
     };
 
@@ -281,6 +287,8 @@ fn should_import_from_parity() {
         cipher_iv: arr!(&"1654e558f82fe0eeb177ae9cef3ff592".from_hex().unwrap(),
                         CIPHER_IV_BYTES),
         name: Some("".to_string()),
+        description: None,
+        visible: None,
         meta: Some(emerald::keystore::meta::MetaInfo),
     };
 